@@ -0,0 +1,865 @@
+use rand::Rng;
+use rodio::{Sink, Source};
+use std::time::Duration;
+
+// Convierte las muestras de un WAV mono en tablas de igual tamaño: si `frames`
+// vale 1 se usa el archivo entero como una única tabla de un ciclo; si vale más,
+// el archivo se corta en `frames` trozos iguales para alimentar el morphing.
+fn wav_to_tables(path: &str, frames: usize) -> hound::Result<Vec<Vec<f32>>> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+
+    // `wav_to_tables`/`from_wav` solo saben leer WAV mono: un archivo estéreo
+    // entrelaza L/R muestra a muestra, y trocearlo como si fuera mono mezclaría
+    // ambos canales dentro de cada tabla en vez de producir el audio esperado.
+    if spec.channels != 1 {
+        return Err(hound::Error::Unsupported);
+    }
+
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<hound::Result<_>>()?,
+        hound::SampleFormat::Int => {
+            // Solo se aceptan 16 y 24 bits. A 32 bits el desplazamiento
+            // `1 << (bits - 1)` se sale del rango de i32
+            // y da la vuelta a i32::MIN, invirtiendo el signo de cada muestra.
+            if spec.bits_per_sample != 16 && spec.bits_per_sample != 24 {
+                return Err(hound::Error::Unsupported);
+            }
+            let max_value = (1_i32 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|s| s as f32 / max_value))
+                .collect::<hound::Result<_>>()?
+        }
+    };
+
+    let frame_size = samples.len() / frames.max(1);
+    if frame_size == 0 {
+        return Err(hound::Error::FormatError(
+            "WAV file has too few samples to fill even one table",
+        ));
+    }
+    let tables = samples
+        .chunks_exact(frame_size)
+        .take(frames.max(1))
+        .map(|chunk| chunk.to_vec())
+        .collect();
+
+    Ok(tables)
+}
+
+// Tamaño de las tablas generadas por suma aditiva. Más grande que las 64 muestras
+// de la tabla ingenua para que los armónicos agudos no pierdan resolución.
+const BAND_LIMITED_TABLE_SIZE: usize = 2048;
+
+// Frecuencia fundamental más grave que cubre el generador de bandas (un poco por
+// debajo del La más grave de un piano), para que la primera octava sea audible.
+const BAND_LIMITED_BASE_FREQUENCY: f32 = 20.0;
+
+// Variables de un wavetable.
+//
+// `tables` guarda una o varias tablas de igual tamaño: una tabla ingenua usa una
+// sola, mientras que un oscilador limitado en banda guarda una por octava, o un
+// oscilador morphing guarda varios timbres a recorrer. `position` indica qué
+// tabla (o mezcla de dos tablas contiguas) se está leyendo en cada momento.
+pub struct WaveTableOscillator {
+    sample_rate: u32,
+    tables: Vec<Vec<f32>>,
+    band_max_frequency: Vec<f32>,
+    position: f32,
+    index: f32,
+    index_increment: f32,
+    base_frequency: f32,
+    detune_cents: f32,
+    glide_increment_step: f32,
+    glide_remaining_samples: u32,
+}
+
+impl WaveTableOscillator {
+    // Constructor que inicia el oscilador a partir de una única wavetable
+    fn new(sample_rate: u32, wave_table: Vec<f32>) -> WaveTableOscillator {
+        WaveTableOscillator {
+            sample_rate,
+            tables: vec![wave_table],
+            band_max_frequency: vec![f32::INFINITY],
+            position: 0.0,
+            index: 0.0,
+            index_increment: 0.0,
+            base_frequency: 0.0,
+            detune_cents: 0.0,
+            glide_increment_step: 0.0,
+            glide_remaining_samples: 0,
+        }
+    }
+
+    // Construye un oscilador limitado en banda: genera, por suma aditiva, una
+    // tabla por octava a partir del espectro armónico ideal `spectrum`, para que
+    // `set_frequency` pueda elegir siempre la tabla que no aliasea.
+    pub fn from_band_limited(sample_rate: u32, spectrum: HarmonicSpectrum) -> WaveTableOscillator {
+        let (tables, band_max_frequency) = BandLimitedWaveTable::new(spectrum, sample_rate).build();
+
+        WaveTableOscillator {
+            sample_rate,
+            tables,
+            band_max_frequency,
+            position: 0.0,
+            index: 0.0,
+            index_increment: 0.0,
+            base_frequency: 0.0,
+            detune_cents: 0.0,
+            glide_increment_step: 0.0,
+            glide_remaining_samples: 0,
+        }
+    }
+
+    // Construye un oscilador morphing: recorre, mediante `set_position`, una
+    // secuencia de tablas de igual tamaño en lugar de una sola forma de onda fija
+    pub fn from_tables(sample_rate: u32, tables: Vec<Vec<f32>>) -> WaveTableOscillator {
+        let band_max_frequency = vec![f32::INFINITY; tables.len()];
+
+        WaveTableOscillator {
+            sample_rate,
+            tables,
+            band_max_frequency,
+            position: 0.0,
+            index: 0.0,
+            index_increment: 0.0,
+            base_frequency: 0.0,
+            detune_cents: 0.0,
+            glide_increment_step: 0.0,
+            glide_remaining_samples: 0,
+        }
+    }
+
+    // Construye un oscilador a partir de un WAV mono leído con `hound`: con
+    // `frames` a 1 el archivo se usa entero como un único ciclo; con más,
+    // se reparte en esa cantidad de tablas para poder recorrerlas con `set_position`.
+    pub fn from_wav(
+        sample_rate: u32,
+        path: &str,
+        frames: usize,
+    ) -> hound::Result<WaveTableOscillator> {
+        let tables = wav_to_tables(path, frames)?;
+        Ok(WaveTableOscillator::from_tables(sample_rate, tables))
+    }
+
+    // Fija la posición de morphing en `[0, num_tables - 1]`. La parte entera
+    // selecciona la tabla base y la parte fraccionaria mezcla con la siguiente,
+    // permitiendo barrer el timbre de forma continua entre varias tablas.
+    pub fn set_position(&mut self, position: f32) {
+        let max_position = (self.tables.len() - 1) as f32;
+        self.position = position.clamp(0.0, max_position);
+    }
+
+    // Construye un oscilador ingenuo de una sola tabla con una de las formas de
+    // onda clásicas de WaveShape
+    pub fn from_shape(sample_rate: u32, size: usize, shape: WaveShape) -> WaveTableOscillator {
+        let mut wave_table = Vec::with_capacity(size);
+
+        match shape {
+            WaveShape::Sine => {
+                for n in 0..size {
+                    wave_table
+                        .push((2.0 * std::f32::consts::PI * n as f32 / size as f32).sin());
+                }
+            }
+            WaveShape::Saw => {
+                for n in 0..size {
+                    wave_table.push(2.0 * (n as f32 / size as f32) - 1.0);
+                }
+            }
+            WaveShape::Square => {
+                for n in 0..size {
+                    wave_table.push(if n < size / 2 { 1.0 } else { -1.0 });
+                }
+            }
+            WaveShape::Triangle => {
+                for n in 0..size {
+                    let phase = n as f32 / size as f32;
+                    wave_table.push(if phase < 0.5 {
+                        4.0 * phase - 1.0
+                    } else {
+                        3.0 - 4.0 * phase
+                    });
+                }
+            }
+            WaveShape::Noise => {
+                let mut rng = rand::thread_rng();
+                for _ in 0..size {
+                    wave_table.push(rng.gen_range(-1.0..1.0));
+                }
+            }
+        }
+
+        WaveTableOscillator::new(sample_rate, wave_table)
+    }
+
+    // Frequencia del oscilador.
+    // Elige la tabla cuya banda cubre la frecuencia pedida y calcula el incremento
+    // del índice en función de la frecuencia y el tamaño de esa tabla
+    pub fn set_frequency(&mut self, frequency: f32) {
+        let table_index = self
+            .band_max_frequency
+            .iter()
+            .position(|&max_frequency| frequency < max_frequency)
+            .unwrap_or(self.band_max_frequency.len() - 1);
+        self.position = table_index as f32;
+
+        self.base_frequency = frequency;
+        self.glide_remaining_samples = 0;
+        self.index_increment = self.frequency_to_increment(frequency);
+    }
+
+    // Desafina el oscilador `cents` centésimas de semitono respecto a la frecuencia
+    // actual, como el `detune` de un `OscillatorNode` de Web Audio
+    pub fn set_detune(&mut self, cents: f32) {
+        self.detune_cents = cents;
+        let target_increment = self.frequency_to_increment(self.base_frequency);
+
+        if self.glide_remaining_samples > 0 {
+            // Un glide sigue en marcha: no saltar de golpe al nuevo pitch, sino
+            // recalcular el paso para que siga llegando al destino (ya desafinado)
+            // justo cuando el glide termine.
+            self.glide_increment_step =
+                (target_increment - self.index_increment) / self.glide_remaining_samples as f32;
+        } else {
+            self.index_increment = target_increment;
+        }
+    }
+
+    // Desliza (portamento) la frecuencia hasta `frequency` en `seconds` segundos,
+    // avanzando linealmente `index_increment` una fracción por cada muestra leída
+    pub fn glide_to(&mut self, frequency: f32, seconds: f32) {
+        self.base_frequency = frequency;
+
+        let target_increment = self.frequency_to_increment(frequency);
+        let total_samples = (seconds * self.sample_rate as f32).round().max(1.0) as u32;
+
+        self.glide_increment_step = (target_increment - self.index_increment) / total_samples as f32;
+        self.glide_remaining_samples = total_samples;
+    }
+
+    // Incremento de índice correspondiente a `frequency`, ya con el detune aplicado
+    fn frequency_to_increment(&self, frequency: f32) -> f32 {
+        let detuned_frequency = frequency * 2f32.powf(self.detune_cents / 1200.0);
+        detuned_frequency * self.wave_table().len() as f32 / self.sample_rate as f32
+    }
+
+    // Tabla base actualmente seleccionada, según la última llamada a
+    // `set_frequency` o `set_position`
+    fn wave_table(&self) -> &Vec<f32> {
+        &self.tables[self.position as usize]
+    }
+
+    // Obtiene un sample del oscilador, realizando interpolación lineal
+    fn get_sample(&mut self) -> f32 {
+        let sample = self.lerp();
+        self.index += self.index_increment;
+        self.index %= self.wave_table().len() as f32;
+
+        if self.glide_remaining_samples > 0 {
+            self.index_increment += self.glide_increment_step;
+            self.glide_remaining_samples -= 1;
+        }
+
+        sample
+    }
+
+    // Interpola linealmente dentro de `wave_table` en la posición fraccionaria `index`
+    fn lerp_table(wave_table: &[f32], index: f32) -> f32 {
+        let truncated_index = index as usize;
+        let next_index = (truncated_index + 1) % wave_table.len();
+
+        let next_index_weight = index - truncated_index as f32;
+        let truncated_index_weight = 1.0 - next_index_weight;
+
+        truncated_index_weight * wave_table[truncated_index]
+            + next_index_weight * wave_table[next_index]
+    }
+
+    // Realiza la interpolación lineal dentro de la tabla y, si `position` cae
+    // entre dos tablas, mezcla (crossfade) ambos resultados según su parte
+    // fraccionaria para producir el morphing entre timbres
+    fn lerp(&self) -> f32 {
+        let lower_table_index = self.position as usize;
+        let upper_table_index = (lower_table_index + 1).min(self.tables.len() - 1);
+        let morph_weight = self.position - lower_table_index as f32;
+
+        let lower_sample = Self::lerp_table(&self.tables[lower_table_index], self.index);
+        let upper_sample = Self::lerp_table(&self.tables[upper_table_index], self.index);
+
+        lower_sample * (1.0 - morph_weight) + upper_sample * morph_weight
+    }
+}
+
+// Permite tratar el oscilador como un iterador que produce muestras de audio
+impl Iterator for WaveTableOscillator {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        Some(self.get_sample())
+    }
+}
+
+// Define métodos requeridos para que WaveTableOscillator sea una fuente de audio
+// para que pueda ser reproducida por Rodio
+impl Source for WaveTableOscillator {
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+// Etapas por las que atraviesa un Envelope entre `note_on` y que termine de apagarse
+enum EnvelopeStage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+}
+
+// Envolvente de amplitud ADSR. `attack`, `decay` y `release` se miden en segundos;
+// `sustain` es el nivel sostenido en [0, 1]. `advance` se llama una vez por muestra
+// y devuelve la ganancia a aplicar en ese instante.
+struct Envelope {
+    sample_rate: u32,
+    attack: f32,
+    decay: f32,
+    sustain: f32,
+    release: f32,
+    stage: EnvelopeStage,
+    stage_time: f32,
+    level: f32,
+    release_start_level: f32,
+}
+
+impl Envelope {
+    fn new(sample_rate: u32, attack: f32, decay: f32, sustain: f32, release: f32) -> Envelope {
+        Envelope {
+            sample_rate,
+            attack,
+            decay,
+            sustain,
+            release,
+            stage: EnvelopeStage::Idle,
+            stage_time: 0.0,
+            level: 0.0,
+            release_start_level: 0.0,
+        }
+    }
+
+    // Dispara el ataque: el nivel sube desde 0 hasta 1 en `attack` segundos
+    fn note_on(&mut self) {
+        self.stage = EnvelopeStage::Attack;
+        self.stage_time = 0.0;
+    }
+
+    // Dispara la liberación: la nota empieza a apagarse desde el nivel actual
+    fn note_off(&mut self) {
+        self.release_start_level = self.level;
+        self.stage = EnvelopeStage::Release;
+        self.stage_time = 0.0;
+    }
+
+    // Avanza un periodo de muestra y devuelve la ganancia actual de la envolvente
+    fn advance(&mut self) -> f32 {
+        match self.stage {
+            EnvelopeStage::Idle => {
+                self.level = 0.0;
+            }
+            EnvelopeStage::Attack => {
+                self.level = if self.attack <= 0.0 {
+                    1.0
+                } else {
+                    (self.stage_time / self.attack).min(1.0)
+                };
+                if self.stage_time >= self.attack {
+                    self.stage = EnvelopeStage::Decay;
+                    self.stage_time = 0.0;
+                }
+            }
+            EnvelopeStage::Decay => {
+                self.level = if self.decay <= 0.0 {
+                    self.sustain
+                } else {
+                    1.0 - (1.0 - self.sustain) * (self.stage_time / self.decay).min(1.0)
+                };
+                if self.stage_time >= self.decay {
+                    self.stage = EnvelopeStage::Sustain;
+                    self.stage_time = 0.0;
+                }
+            }
+            EnvelopeStage::Sustain => {
+                self.level = self.sustain;
+            }
+            EnvelopeStage::Release => {
+                self.level = if self.release <= 0.0 {
+                    0.0
+                } else {
+                    self.release_start_level * (1.0 - (self.stage_time / self.release).min(1.0))
+                };
+                if self.stage_time >= self.release {
+                    self.stage = EnvelopeStage::Idle;
+                    self.stage_time = 0.0;
+                }
+            }
+        }
+
+        self.stage_time += 1.0 / self.sample_rate as f32;
+        self.level
+    }
+}
+
+// Envuelve un WaveTableOscillator con una Envelope ADSR para que las notas tengan
+// ataque y caída en vez de encenderse y apagarse de golpe
+struct EnvelopedOscillator {
+    oscillator: WaveTableOscillator,
+    envelope: Envelope,
+}
+
+impl EnvelopedOscillator {
+    fn new(oscillator: WaveTableOscillator, envelope: Envelope) -> EnvelopedOscillator {
+        EnvelopedOscillator {
+            oscillator,
+            envelope,
+        }
+    }
+
+    fn set_frequency(&mut self, frequency: f32) {
+        self.oscillator.set_frequency(frequency);
+    }
+
+    // Dispara el ataque de la envolvente
+    fn note_on(&mut self) {
+        self.envelope.note_on();
+    }
+
+    // Dispara la liberación de la envolvente
+    fn note_off(&mut self) {
+        self.envelope.note_off();
+    }
+}
+
+// Produce muestras del oscilador multiplicadas por la ganancia de la envolvente,
+// avanzando un periodo de muestra por cada llamada a `next`
+impl Iterator for EnvelopedOscillator {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.oscillator.get_sample();
+        Some(sample * self.envelope.advance())
+    }
+}
+
+impl Source for EnvelopedOscillator {
+    fn channels(&self) -> u16 {
+        self.oscillator.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.oscillator.sample_rate()
+    }
+
+    fn current_frame_len(&self) -> Option<usize> {
+        self.oscillator.current_frame_len()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.oscillator.total_duration()
+    }
+}
+
+// Paleta de formas de onda clásicas que WaveTableOscillator::from_shape puede generar
+#[derive(Clone, Copy)]
+pub enum WaveShape {
+    Sine,
+    Saw,
+    Square,
+    Triangle,
+    Noise,
+}
+
+// Describe el espectro armónico ideal de una forma de onda clásica. Se usa para
+// sintetizar, por suma aditiva, las tablas limitadas en banda de BandLimitedWaveTable.
+pub enum HarmonicSpectrum {
+    Saw,
+    Square,
+}
+
+impl HarmonicSpectrum {
+    // Amplitud del armónico `n` (1-indexado) de este espectro ideal
+    fn amplitude(&self, n: u32) -> f32 {
+        match self {
+            HarmonicSpectrum::Saw => 1.0 / n as f32,
+            HarmonicSpectrum::Square => {
+                if n % 2 == 1 {
+                    1.0 / n as f32
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+// Genera un "mip-map" de wavetables, una por octava, conteniendo en cada una
+// solo los armónicos que se mantienen por debajo de Nyquist. Esto evita el
+// aliasing que produce reproducir una única tabla ingenua a frecuencias agudas.
+struct BandLimitedWaveTable {
+    spectrum: HarmonicSpectrum,
+    sample_rate: u32,
+}
+
+impl BandLimitedWaveTable {
+    fn new(spectrum: HarmonicSpectrum, sample_rate: u32) -> BandLimitedWaveTable {
+        BandLimitedWaveTable {
+            spectrum,
+            sample_rate,
+        }
+    }
+
+    // Construye las tablas por octava junto con la frecuencia fundamental más aguda
+    // que cada una admite antes de empezar a aliasear.
+    fn build(&self) -> (Vec<Vec<f32>>, Vec<f32>) {
+        let nyquist = self.sample_rate as f32 / 2.0;
+        let mut tables = Vec::new();
+        let mut band_max_frequency = Vec::new();
+
+        let mut base_frequency = BAND_LIMITED_BASE_FREQUENCY;
+        while base_frequency < nyquist {
+            // La tabla se usa para toda frecuencia hasta `base_frequency * 2` (el
+            // borde superior de la banda), así que los armónicos deben limitarse
+            // a los que siguen por debajo de Nyquist en ese borde, no en el
+            // inferior, o la nota más aguda de la banda seguirá aliaseando.
+            let band_top_frequency = base_frequency * 2.0;
+            // En la última banda el borde superior puede caer tan cerca de
+            // Nyquist que ni el armónico 1 cabría (p. ej. top=40960Hz con
+            // Nyquist=22050Hz). Sin este `.max(1)` esa banda quedaría con una
+            // tabla totalmente en silencio, y como cubre toda frecuencia por
+            // encima de su base, cualquier nota ahí se apagaría del todo en
+            // vez de sonar (aunque sea sin el filtrado anti-aliasing pleno).
+            let max_harmonic = Self::max_harmonic_for_band(nyquist, band_top_frequency).max(1);
+            let mut table = vec![0.0; BAND_LIMITED_TABLE_SIZE];
+
+            for n in 1..=max_harmonic {
+                let amplitude = self.spectrum.amplitude(n);
+                if amplitude == 0.0 {
+                    continue;
+                }
+                for (i, sample) in table.iter_mut().enumerate() {
+                    *sample += amplitude
+                        * (2.0 * std::f32::consts::PI * n as f32 * i as f32
+                            / BAND_LIMITED_TABLE_SIZE as f32)
+                            .sin();
+                }
+            }
+
+            tables.push(table);
+            band_max_frequency.push(band_top_frequency);
+            base_frequency *= 2.0;
+        }
+
+        (tables, band_max_frequency)
+    }
+
+    // Mayor armónico que cabe en una banda sin superar Nyquist, evaluado en el
+    // borde superior de la banda (la frecuencia más aguda que esa tabla cubre)
+    fn max_harmonic_for_band(nyquist: f32, band_top_frequency: f32) -> u32 {
+        (nyquist / band_top_frequency).floor() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn band_limited_tables_never_alias_past_nyquist() {
+        let sample_rate = 44100;
+        let nyquist = sample_rate as f32 / 2.0;
+        let (_, band_max_frequency) =
+            BandLimitedWaveTable::new(HarmonicSpectrum::Saw, sample_rate).build();
+
+        for band_top_frequency in &band_max_frequency {
+            // La banda más aguda puede necesitar el clamp a 1 armónico de
+            // `build` cuando su borde cae por encima de Nyquist; en ese caso
+            // el propio borde ya no es una frecuencia reproducible sin
+            // aliasear y no aplica esta comprobación (ver el siguiente test).
+            let raw_max_harmonic =
+                BandLimitedWaveTable::max_harmonic_for_band(nyquist, *band_top_frequency);
+            if raw_max_harmonic == 0 {
+                continue;
+            }
+            assert!(
+                raw_max_harmonic as f32 * band_top_frequency <= nyquist,
+                "band up to {band_top_frequency}Hz keeps harmonic {raw_max_harmonic}, which aliases past Nyquist ({nyquist}Hz)"
+            );
+        }
+    }
+
+    #[test]
+    fn band_limited_tables_are_never_silent() {
+        let sample_rate = 44100;
+        let (tables, band_max_frequency) =
+            BandLimitedWaveTable::new(HarmonicSpectrum::Saw, sample_rate).build();
+
+        for (table, band_top_frequency) in tables.iter().zip(&band_max_frequency) {
+            assert!(
+                table.iter().any(|sample| sample.abs() > 1e-6),
+                "band up to {band_top_frequency}Hz produced an all-zero table, so notes in it wouldn't sound at all"
+            );
+        }
+    }
+
+    #[test]
+    fn morph_position_crossfades_between_tables() {
+        let mut oscillator =
+            WaveTableOscillator::from_tables(44100, vec![vec![0.0; 4], vec![1.0; 4]]);
+
+        oscillator.set_position(0.25);
+        assert_eq!(oscillator.lerp(), 0.25);
+
+        oscillator.set_position(1.0);
+        assert_eq!(oscillator.lerp(), 1.0);
+
+        // Fuera de rango se recorta a la última tabla, no hace panic
+        oscillator.set_position(5.0);
+        assert_eq!(oscillator.lerp(), 1.0);
+    }
+
+    #[test]
+    fn set_detune_during_glide_retargets_without_snapping() {
+        let mut oscillator = WaveTableOscillator::from_shape(44100, 64, WaveShape::Sine);
+        oscillator.set_frequency(440.0);
+
+        oscillator.glide_to(880.0, 0.01);
+        for _ in 0..100 {
+            oscillator.get_sample();
+        }
+        let increment_mid_glide = oscillator.index_increment;
+
+        // Desafinar a mitad del glide no debe saltar de golpe al nuevo pitch...
+        oscillator.set_detune(1200.0); // una octava arriba
+        let target_increment = oscillator.frequency_to_increment(880.0);
+        assert!((oscillator.index_increment - increment_mid_glide).abs() < 1e-3);
+        assert!((oscillator.index_increment - target_increment).abs() > 1e-3);
+
+        // ...pero sí debe alcanzar el destino (ya desafinado) cuando el glide termine
+        let remaining = oscillator.glide_remaining_samples;
+        for _ in 0..remaining {
+            oscillator.get_sample();
+        }
+        assert!((oscillator.index_increment - target_increment).abs() < 1e-2);
+    }
+
+    fn write_test_wav(path: &std::path::Path, bits_per_sample: u16, samples: &[i32]) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44100,
+            bits_per_sample,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        for sample in samples {
+            writer.write_sample(*sample).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    #[test]
+    fn from_wav_reads_16_bit_samples_back_into_a_single_table() {
+        let path = std::env::temp_dir().join("wavetable_synth_test_16bit.wav");
+        write_test_wav(&path, 16, &[0, i16::MAX as i32, 0, i16::MIN as i32]);
+
+        let oscillator = WaveTableOscillator::from_wav(44100, path.to_str().unwrap(), 1).unwrap();
+
+        assert_eq!(oscillator.tables.len(), 1);
+        assert_eq!(oscillator.tables[0].len(), 4);
+        assert!((oscillator.tables[0][1] - 1.0).abs() < 1e-3);
+        assert!((oscillator.tables[0][3] + 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn from_wav_rejects_unsupported_bit_depths_instead_of_overflowing() {
+        let path = std::env::temp_dir().join("wavetable_synth_test_32bit.wav");
+        write_test_wav(&path, 32, &[0, 1, 2, 3]);
+
+        let result = WaveTableOscillator::from_wav(44100, path.to_str().unwrap(), 1);
+
+        assert!(matches!(result, Err(hound::Error::Unsupported)));
+    }
+
+    #[test]
+    fn from_wav_rejects_files_too_short_to_fill_a_table() {
+        let path = std::env::temp_dir().join("wavetable_synth_test_empty.wav");
+        write_test_wav(&path, 16, &[]);
+
+        let result = WaveTableOscillator::from_wav(44100, path.to_str().unwrap(), 4);
+
+        assert!(matches!(result, Err(hound::Error::FormatError(_))));
+    }
+
+    #[test]
+    fn from_wav_rejects_stereo_files() {
+        let path = std::env::temp_dir().join("wavetable_synth_test_stereo.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 44100,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for sample in [0_i16, 0, 1, 1, 2, 2, 3, 3] {
+            writer.write_sample(sample).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let result = WaveTableOscillator::from_wav(44100, path.to_str().unwrap(), 1);
+
+        assert!(matches!(result, Err(hound::Error::Unsupported)));
+    }
+}
+
+// Un paso del patrón del Sequencer: frecuencia en Hz (0 para silencio) y
+// duración en beats, que junto al BPM determina cuánto dura el paso en segundos
+pub struct Step {
+    pub frequency: f32,
+    pub duration_beats: f32,
+}
+
+// Envuelve una voz (EnvelopedOscillator) para que dure exactamente
+// `remaining_samples` muestras, dejando que el Sink avance solo al siguiente
+// paso del patrón, y dispara su liberación un poco antes del final para que
+// la nota no se corte de golpe
+struct SteppedNote {
+    voice: EnvelopedOscillator,
+    // Muestras restantes en las que se dispara `note_off` (la duración del release)
+    note_off_after: u32,
+    remaining_samples: u32,
+}
+
+impl Iterator for SteppedNote {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.remaining_samples == 0 {
+            return None;
+        }
+
+        if self.remaining_samples == self.note_off_after {
+            self.voice.note_off();
+        }
+
+        self.remaining_samples -= 1;
+        self.voice.next()
+    }
+}
+
+impl Source for SteppedNote {
+    fn channels(&self) -> u16 {
+        self.voice.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.voice.sample_rate()
+    }
+
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.remaining_samples as usize)
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+// Reproduce un patrón de pasos (frecuencia, duración) a un tempo en BPM, como
+// el sistema de patrones de audact: calcula la duración de cada paso a partir
+// del tempo, retriggerea la envolvente de una voz nueva en cada paso y los
+// encola en un `Sink` de Rodio para que se reproduzcan en orden.
+pub struct Sequencer {
+    steps: Vec<Step>,
+    bpm: f32,
+    sample_rate: u32,
+}
+
+// Agrupa la forma de onda y la envolvente ADSR de una voz, para que
+// Sequencer::play_once/play no tengan que recibir cada parámetro por separado
+// (y transponer ataque/decay/release a ciegas no sea un error silencioso).
+pub struct VoiceConfig {
+    pub shape: WaveShape,
+    pub table_size: usize,
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl Sequencer {
+    pub fn new(steps: Vec<Step>, bpm: f32, sample_rate: u32) -> Sequencer {
+        Sequencer {
+            steps,
+            bpm,
+            sample_rate,
+        }
+    }
+
+    // Duración en segundos de un paso de `duration_beats` beats, a este tempo
+    fn step_duration(&self, duration_beats: f32) -> f32 {
+        duration_beats * 60.0 / self.bpm
+    }
+
+    // Encola en `sink` una pasada completa del patrón con la voz descrita por
+    // `voice`; cada paso recibe una voz nueva para que el ataque se retriggeree
+    // siempre.
+    pub fn play_once(&self, sink: &Sink, voice: &VoiceConfig) {
+        for step in &self.steps {
+            let total_samples = (self.step_duration(step.duration_beats) * self.sample_rate as f32)
+                .round()
+                .max(1.0) as u32;
+            let release_samples = (voice.release * self.sample_rate as f32).round() as u32;
+            let note_off_after = release_samples.min(total_samples);
+
+            let oscillator =
+                WaveTableOscillator::from_shape(self.sample_rate, voice.table_size, voice.shape);
+            let envelope = Envelope::new(
+                self.sample_rate,
+                voice.attack,
+                voice.decay,
+                voice.sustain,
+                voice.release,
+            );
+            let mut enveloped_oscillator = EnvelopedOscillator::new(oscillator, envelope);
+
+            if step.frequency > 0.0 {
+                enveloped_oscillator.set_frequency(step.frequency);
+                enveloped_oscillator.note_on();
+            }
+
+            sink.append(SteppedNote {
+                voice: enveloped_oscillator,
+                note_off_after,
+                remaining_samples: total_samples,
+            });
+        }
+    }
+
+    // Encola el patrón `loops` veces seguidas, para que se repita en bucle
+    pub fn play(&self, sink: &Sink, voice: &VoiceConfig, loops: u32) {
+        for _ in 0..loops {
+            self.play_once(sink, voice);
+        }
+    }
+}